@@ -1,47 +1,121 @@
 use actix_multipart::MultipartError;
 use actix_multipart::form::{MultipartForm, MultipartFormConfig, tempfile::TempFile};
 use actix_web::{
-    App, HttpRequest, HttpResponse, HttpServer, Responder, Result,
+    App, HttpRequest, HttpResponse, HttpServer, Responder, Result, ResponseError,
     dev::ServiceResponse,
     get,
-    http::header,
+    http::{StatusCode, header},
     middleware::{ErrorHandlerResponse, ErrorHandlers, Logger},
     post, web,
 };
-use log::{error, info};
+use image::ImageFormat;
+use image::error::{DecodingError, ImageFormatHint};
+use jxl_oxide::JxlImage;
+use log::{error, info, warn};
+use lru::LruCache;
 use ocrs::{ImageSource, OcrEngine, OcrEngineParams};
 use reqwest::get as fetch;
 use rten::Model;
-use serde::Serialize;
-use serde_json;
+use rten_imageproc::BoundingRect;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::error::Error;
+use std::io::Cursor;
+use std::net::{IpAddr, SocketAddr};
+use thiserror::Error as ThisError;
+use utoipa::{OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
 
+/// Maximum number of bytes accepted for a single image, whether uploaded via
+/// multipart or downloaded from a remote URL.
+const MAX_IMAGE_BYTES: usize = 15 * 1024 * 1024; // 15 MB
 
-#[derive(Serialize)]
+/// Number of recognition results kept in the in-memory result cache.
+const CACHE_CAPACITY: usize = 1024;
+
+/// `max-age` advertised on recognition responses, in seconds.
+const CACHE_MAX_AGE: u32 = 86_400; // 1 day
+
+/// Maximum number of files accepted in a single batch request.
+const MAX_BATCH_FILES: usize = 32;
+
+/// Maximum combined size of all files in a single multipart request.
+const MAX_REQUEST_BYTES: usize = 128 * 1024 * 1024; // 128 MB
+
+
+#[derive(Serialize, ToSchema)]
 struct ApiResponse<T> {
     status: u16,
     message: String,
     data: Option<T>,
 }
 
-fn ok_response<T: Serialize>(data: T) -> HttpResponse {
-    HttpResponse::Ok().json(ApiResponse {
-        status: 200,
-        message: "OK".to_string(),
-        data: Some(data),
-    })
+/// Every failure a recognition request can surface.
+///
+/// Each variant maps to a single HTTP status and reuses the `ApiResponse`
+/// envelope (`status`/`message`/`data: null`) via the `ResponseError` impl, so
+/// the error format stays identical to `global_error_handler`.
+#[derive(Debug, ThisError)]
+enum AppError {
+    #[error("Failed to read uploaded image")]
+    ReadUpload(#[from] std::io::Error),
+
+    #[error("Invalid image format")]
+    DecodeImage(#[from] image::ImageError),
+
+    #[error("Unsupported image format (detected {detected}, declared {declared})")]
+    UnsupportedFormat { detected: String, declared: String },
+
+    #[error("Failed to prepare OCR input")]
+    PrepareInput(#[source] Box<dyn Error + Send + Sync>),
+
+    #[error("Failed to detect words")]
+    DetectWords(#[source] Box<dyn Error + Send + Sync>),
+
+    #[error("Failed to recognize text")]
+    RecognizeText(#[source] Box<dyn Error + Send + Sync>),
+
+    #[error("Failed to download image from URL")]
+    Download(#[source] Box<dyn Error + Send + Sync>),
+
+    #[error("Payload exceeds the size limit (15 MB per image, 128 MB per request)")]
+    PayloadTooLarge,
+
+    #[error("Too many files in request ({count}, max {max})")]
+    TooManyFiles { count: usize, max: usize },
 }
 
-fn error_response(status: u16, message: &str) -> HttpResponse {
-    HttpResponse::build(
-        actix_web::http::StatusCode::from_u16(status)
-            .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR),
-    )
-    .json(ApiResponse::<()> {
-        status,
-        message: message.to_string(),
-        data: None,
-    })
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::ReadUpload(_) | AppError::DecodeImage(_) | AppError::Download(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            AppError::UnsupportedFormat { .. } => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::PayloadTooLarge | AppError::TooManyFiles { .. } => {
+                StatusCode::PAYLOAD_TOO_LARGE
+            }
+            AppError::PrepareInput(_)
+            | AppError::DetectWords(_)
+            | AppError::RecognizeText(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let Some(source) = self.source() {
+            error!("{}: {}", self, source);
+        } else {
+            error!("{}", self);
+        }
+        let status = self.status_code();
+        HttpResponse::build(status).json(ApiResponse::<()> {
+            status: status.as_u16(),
+            message: self.to_string(),
+            data: None,
+        })
+    }
 }
 
 fn handle_multipart_error(err: MultipartError, _req: &HttpRequest) -> actix_web::Error {
@@ -90,69 +164,172 @@ fn global_error_handler<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerRespon
 
 struct AppState {
     engine: OcrEngine,
+    cache: Mutex<LruCache<String, Vec<String>>>,
 }
 
-#[derive(Debug, MultipartForm)]
+#[derive(Debug, MultipartForm, ToSchema)]
 struct UploadForm {
-    #[multipart(limit = "15MB")]
-    file: TempFile,
+    // The per-file 15 MB cap is enforced in-handler (see `recognize_file`) so an
+    // oversized file becomes one failed `BatchItem` instead of a request-level
+    // 400; the field limit only bounds the whole request, matching the
+    // `MultipartFormConfig::total_limit`.
+    #[multipart(limit = "128MB")]
+    #[schema(value_type = Vec<String>, format = Binary)]
+    files: Vec<TempFile>,
 }
 
-#[post("/v1/recognize")]
-async fn recognize(
-    state: web::Data<AppState>,
-    MultipartForm(form): MultipartForm<UploadForm>,
-) -> impl Responder {
-    let engine = &state.engine;
-
-    let image_bytes = match std::fs::read(&form.file.file) {
-        Ok(bytes) => bytes,
-        Err(err) => {
-            error!("Failed to read uploaded image: {}", err);
-            return error_response(400, "Failed to read uploaded image");
-        }
-    };
+/// Per-file result in a batch recognition response.
+///
+/// `lines` holds either the flat recognized strings (default) or structured
+/// [`TextLine`] geometry (detailed mode); `T` selects which.
+#[derive(Serialize, ToSchema)]
+struct BatchItem<T> {
+    filename: String,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lines: Option<Vec<T>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
-    let img = match image::load_from_memory(&image_bytes) {
-        Ok(image) => image.into_rgb8(),
-        Err(err) => {
-            error!("Invalid image format: {}", err);
-            return error_response(400, "Invalid image format");
-        }
-    };
+/// Documentation-only union of the two bodies `/v1/recognize` can return
+/// under its single `200` status: the flat-string shape (default) or the
+/// structured-geometry shape (`?format=detailed`). Never constructed; it
+/// exists purely so the OpenAPI spec's `200` response covers both via
+/// `oneOf` instead of only documenting whichever variant was listed last.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum RecognizeResponseBody {
+    Plain(ApiResponse<Vec<BatchItem<String>>>),
+    Detailed(ApiResponse<Vec<BatchItem<TextLine>>>),
+}
 
-    let img_source = match ImageSource::from_bytes(img.as_raw(), img.dimensions()) {
-        Ok(src) => src,
-        Err(err) => {
-            error!("Failed to process image: {}", err);
-            return error_response(500, "Failed to process image");
-        }
-    };
+/// Query parameters accepted by the recognition endpoint.
+#[derive(Debug, Deserialize)]
+struct FormatQuery {
+    format: Option<String>,
+}
 
-    let ocr_input = match engine.prepare_input(img_source) {
-        Ok(input) => input,
-        Err(err) => {
-            error!("Failed to prepare OCR input: {}", err);
-            return error_response(500, "Failed to prepare OCR input");
-        }
+#[derive(Debug, Deserialize, ToSchema)]
+struct UrlRequest {
+    url: String,
+}
+
+/// Decode uploaded bytes into an RGB image, sniffing the real format from the
+/// magic number (`image::guess_format`) and cross-checking it against the
+/// caller's declared `Content-Type`.
+///
+/// Formats the `image` crate recognises but cannot decode with the enabled
+/// features (e.g. AVIF or WebP when their decoders are missing) surface as a
+/// `415` with the detected-vs-declared pair rather than a blanket `400`.
+/// JPEG-XL, which `image` does not identify, is detected by its magic number
+/// and decoded via `jxl-oxide`.
+fn decode_image(image_bytes: &[u8], declared: Option<&str>) -> Result<image::RgbImage, AppError> {
+    let guessed = image::guess_format(image_bytes).ok();
+
+    if let (Some(declared), Some(sniffed)) = (declared, guessed)
+        && ImageFormat::from_mime_type(declared).map(|d| d != sniffed).unwrap_or(false)
+    {
+        warn!(
+            "Declared content type {} disagrees with sniffed format {:?}",
+            declared, sniffed
+        );
+    }
+
+    match guessed {
+        Some(format) => match image::load_from_memory_with_format(image_bytes, format) {
+            Ok(img) => Ok(img.into_rgb8()),
+            Err(image::ImageError::Unsupported(_)) => Err(AppError::UnsupportedFormat {
+                detected: format!("{:?}", format),
+                declared: declared.unwrap_or("unknown").to_string(),
+            }),
+            Err(err) => Err(AppError::DecodeImage(err)),
+        },
+        None if is_jpeg_xl(image_bytes) => decode_jpeg_xl(image_bytes),
+        None => Err(AppError::UnsupportedFormat {
+            detected: "unknown".to_string(),
+            declared: declared.unwrap_or("unknown").to_string(),
+        }),
+    }
+}
+
+/// Detect a JPEG-XL stream, which `image::guess_format` does not recognise,
+/// from either its raw codestream or ISOBMFF container signature.
+fn is_jpeg_xl(bytes: &[u8]) -> bool {
+    const CODESTREAM: [u8; 2] = [0xFF, 0x0A];
+    const CONTAINER: [u8; 12] = [
+        0x00, 0x00, 0x00, 0x0C, 0x4A, 0x58, 0x4C, 0x20, 0x0D, 0x0A, 0x87, 0x0A,
+    ];
+    bytes.starts_with(&CODESTREAM) || bytes.starts_with(&CONTAINER)
+}
+
+/// Decode a JPEG-XL image via `jxl-oxide`, wrapping any failure in an
+/// `image::ImageError` so it flows through the existing `DecodeImage` surface.
+fn decode_jpeg_xl(bytes: &[u8]) -> Result<image::RgbImage, AppError> {
+    let jxl_err = |e: Box<dyn Error + Send + Sync>| {
+        AppError::DecodeImage(image::ImageError::Decoding(DecodingError::new(
+            ImageFormatHint::Name("JPEG XL".to_string()),
+            e,
+        )))
     };
 
-    let word_rects = match engine.detect_words(&ocr_input) {
-        Ok(rects) => rects,
-        Err(err) => {
-            error!("Failed to detect words: {}", err);
-            return error_response(500, "Failed to detect words");
+    let image = JxlImage::builder()
+        .read(Cursor::new(bytes))
+        .map_err(jxl_err)?;
+    let render = image.render_frame(0).map_err(jxl_err)?;
+    let frame = render.image_all_channels();
+
+    let width = frame.width() as u32;
+    let height = frame.height() as u32;
+    let channels = frame.channels();
+    let samples = frame.buf();
+
+    let rgb = image::RgbImage::from_fn(width, height, |x, y| {
+        let base = (y as usize * width as usize + x as usize) * channels;
+        let to_u8 = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+        if channels >= 3 {
+            image::Rgb([
+                to_u8(samples[base]),
+                to_u8(samples[base + 1]),
+                to_u8(samples[base + 2]),
+            ])
+        } else {
+            let g = to_u8(samples[base]);
+            image::Rgb([g, g, g])
         }
-    };
+    });
+
+    Ok(rgb)
+}
+
+/// Run the full detection/recognition pipeline over raw image bytes, returning
+/// the recognized lines.
+///
+/// Shared by the multipart upload handler and the remote-URL handler so both
+/// entrypoints classify failures identically.
+fn recognize_bytes(
+    engine: &OcrEngine,
+    image_bytes: &[u8],
+    declared: Option<&str>,
+) -> Result<Vec<String>, AppError> {
+    let img = decode_image(image_bytes, declared)?;
+
+    let img_source = ImageSource::from_bytes(img.as_raw(), img.dimensions())
+        .map_err(|e| AppError::PrepareInput(Box::new(e)))?;
+
+    let ocr_input = engine
+        .prepare_input(img_source)
+        .map_err(|e| AppError::PrepareInput(e.into()))?;
+
+    let word_rects = engine
+        .detect_words(&ocr_input)
+        .map_err(|e| AppError::DetectWords(e.into()))?;
 
     let line_rects = engine.find_text_lines(&ocr_input, &word_rects);
-    let line_texts = match engine.recognize_text(&ocr_input, &line_rects) {
-        Ok(texts) => texts,
-        Err(err) => {
-            error!("Failed to recognize text: {}", err);
-            return error_response(500, "Failed to recognize text");
-        }
-    };
+    let line_texts = engine
+        .recognize_text(&ocr_input, &line_rects)
+        .map_err(|e| AppError::RecognizeText(e.into()))?;
 
     let recognized_text: Vec<String> = line_texts
         .iter()
@@ -165,14 +342,587 @@ async fn recognize(
         "Successfully recognized text; found {} lines",
         recognized_text.len()
     );
-    ok_response(recognized_text)
+    Ok(recognized_text)
+}
+
+/// Axis-aligned bounding box of a detected word, in pixels.
+#[derive(Serialize, ToSchema)]
+struct WordBox {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
 }
 
+/// A recognized line of text together with its geometry: the line's bounding
+/// box and the boxes of the words it contains.
+#[derive(Serialize, ToSchema)]
+struct TextLine {
+    text: String,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    words: Vec<WordBox>,
+}
+
+/// Run the pipeline and retain the geometry `recognize_bytes` discards,
+/// returning each line's text alongside its bounding box and word boxes.
+fn recognize_bytes_detailed(
+    engine: &OcrEngine,
+    image_bytes: &[u8],
+    declared: Option<&str>,
+) -> Result<Vec<TextLine>, AppError> {
+    let img = decode_image(image_bytes, declared)?;
+
+    let img_source = ImageSource::from_bytes(img.as_raw(), img.dimensions())
+        .map_err(|e| AppError::PrepareInput(Box::new(e)))?;
+
+    let ocr_input = engine
+        .prepare_input(img_source)
+        .map_err(|e| AppError::PrepareInput(e.into()))?;
+
+    let word_rects = engine
+        .detect_words(&ocr_input)
+        .map_err(|e| AppError::DetectWords(e.into()))?;
+
+    let line_rects = engine.find_text_lines(&ocr_input, &word_rects);
+    let line_texts = engine
+        .recognize_text(&ocr_input, &line_rects)
+        .map_err(|e| AppError::RecognizeText(e.into()))?;
+
+    let mut lines = Vec::new();
+    for (rects, text) in line_rects.iter().zip(line_texts.iter()) {
+        let text = match text {
+            Some(line) => line.to_string(),
+            None => continue,
+        };
+        if text.len() <= 1 {
+            continue;
+        }
+
+        let words: Vec<WordBox> = rects
+            .iter()
+            .map(|rect| {
+                let b = rect.bounding_rect();
+                WordBox {
+                    x: b.left() as i32,
+                    y: b.top() as i32,
+                    width: b.width() as i32,
+                    height: b.height() as i32,
+                }
+            })
+            .collect();
+
+        let (x, y, width, height) = bounding_box(&words);
+        lines.push(TextLine {
+            text,
+            x,
+            y,
+            width,
+            height,
+            words,
+        });
+    }
+
+    info!(
+        "Successfully recognized text; found {} lines (detailed)",
+        lines.len()
+    );
+    Ok(lines)
+}
+
+/// Union of the given word boxes as `(x, y, width, height)`; zeros when empty.
+fn bounding_box(words: &[WordBox]) -> (i32, i32, i32, i32) {
+    let mut iter = words.iter();
+    let first = match iter.next() {
+        Some(w) => w,
+        None => return (0, 0, 0, 0),
+    };
+    let mut left = first.x;
+    let mut top = first.y;
+    let mut right = first.x + first.width;
+    let mut bottom = first.y + first.height;
+    for w in iter {
+        left = left.min(w.x);
+        top = top.min(w.y);
+        right = right.max(w.x + w.width);
+        bottom = bottom.max(w.y + w.height);
+    }
+    (left, top, right - left, bottom - top)
+}
+
+/// Hex-encoded SHA-256 digest of the image bytes, used as the cache key and the
+/// strong `ETag` for a recognition result.
+fn image_digest(image_bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(image_bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Whether an `If-None-Match` header value matches the given digest.
+fn etag_matches(if_none_match: Option<&str>, digest: &str) -> bool {
+    if_none_match
+        .map(|tag| tag.trim().trim_matches('"') == digest)
+        .unwrap_or(false)
+}
+
+/// `304 Not Modified` carrying the strong `ETag`/`Cache-Control` headers.
+fn not_modified(digest: &str) -> HttpResponse {
+    HttpResponse::NotModified()
+        .insert_header((header::ETAG, format!("\"{}\"", digest)))
+        .insert_header((
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", CACHE_MAX_AGE),
+        ))
+        .finish()
+}
+
+/// Build an `ApiResponse` envelope around `data`, optionally tagged with the
+/// strong `ETag` and `Cache-Control` headers derived from an image digest.
+fn ok_response<T: Serialize>(data: T, digest: Option<&str>) -> HttpResponse {
+    let mut builder = HttpResponse::Ok();
+    if let Some(digest) = digest {
+        builder.insert_header((header::ETAG, format!("\"{}\"", digest)));
+        builder.insert_header((
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", CACHE_MAX_AGE),
+        ));
+    }
+    builder.json(ApiResponse {
+        status: 200,
+        message: "OK".to_string(),
+        data: Some(data),
+    })
+}
+
+/// Resolve the recognized lines for an image through the content-addressed
+/// cache, running the engine pipeline only on a miss and memoizing the result.
+fn lookup_or_recognize(
+    state: &AppState,
+    digest: &str,
+    image_bytes: &[u8],
+    declared: Option<&str>,
+) -> Result<Vec<String>, AppError> {
+    if let Some(cached) = state
+        .cache
+        .lock()
+        .ok()
+        .and_then(|mut cache| cache.get(digest).cloned())
+    {
+        info!("Cache hit for {}", digest);
+        return Ok(cached);
+    }
+
+    let text = recognize_bytes(&state.engine, image_bytes, declared)?;
+    if let Ok(mut cache) = state.cache.lock() {
+        cache.put(digest.to_string(), text.clone());
+    }
+    Ok(text)
+}
+
+/// Resolve a single-image recognition request through the content-addressed
+/// cache.
+///
+/// When the caller's `If-None-Match` matches the image digest, short-circuits
+/// with `304 Not Modified`; otherwise returns the recognized lines tagged with
+/// the strong `ETag` and `Cache-Control` headers.
+fn process_image(
+    state: &AppState,
+    image_bytes: &[u8],
+    declared: Option<&str>,
+    if_none_match: Option<&str>,
+) -> HttpResponse {
+    let digest = image_digest(image_bytes);
+
+    if etag_matches(if_none_match, &digest) {
+        return not_modified(&digest);
+    }
+
+    match lookup_or_recognize(state, &digest, image_bytes, declared) {
+        Ok(text) => ok_response(text, Some(&digest)),
+        Err(err) => err.error_response(),
+    }
+}
+
+/// Recognize text in every image of a multipart batch, in order.
+///
+/// Each file is capped at 15 MB and the combined request at
+/// [`MAX_REQUEST_BYTES`] across at most [`MAX_BATCH_FILES`] files. A failure on
+/// one file is recorded in its [`BatchItem`] and does not abort the rest of the
+/// batch.
+#[utoipa::path(
+    post,
+    path = "/v1/recognize",
+    tag = "recognition",
+    request_body(content = UploadForm, content_type = "multipart/form-data"),
+    params(("format" = Option<String>, Query, description = "Set to `detailed` for structured per-line geometry")),
+    responses(
+        (status = 200, description = "Per-file recognition results: flat strings by default, or per-line text with line and word bounding boxes (no confidence scores) with `?format=detailed`", body = RecognizeResponseBody),
+        (status = 304, description = "Not modified (single-file upload matched If-None-Match)"),
+        (status = 400, description = "Failed to re-read the uploaded file while preparing the single-file cache response"),
+        (status = 413, description = "Too many files or request too large"),
+    )
+)]
+#[post("/v1/recognize")]
+async fn recognize(
+    req: HttpRequest,
+    query: web::Query<FormatQuery>,
+    state: web::Data<AppState>,
+    MultipartForm(form): MultipartForm<UploadForm>,
+) -> Result<HttpResponse, AppError> {
+    if form.files.len() > MAX_BATCH_FILES {
+        return Err(AppError::TooManyFiles {
+            count: form.files.len(),
+            max: MAX_BATCH_FILES,
+        });
+    }
+
+    let total: usize = form.files.iter().map(|f| f.size).sum();
+    if total > MAX_REQUEST_BYTES {
+        return Err(AppError::PayloadTooLarge);
+    }
+
+    // Detailed mode returns per-line geometry; the default stays the flat
+    // `Vec<String>` shape. Both use the uniform `Vec<BatchItem>` envelope so the
+    // response schema never depends on the file count.
+    if wants_detailed(&query) {
+        let items = batch_map(&form.files, |file| recognize_file_detailed(&state, file));
+        return Ok(ok_response(items, None));
+    }
+
+    let items = batch_map(&form.files, |file| recognize_file(&state, file));
+
+    // A single successful upload additionally carries the chunk0-2 conditional
+    // request contract — strong `ETag`, `Cache-Control`, and
+    // `If-None-Match`→304 — over the one file's digest. A multi-file batch has
+    // no single digest, so it omits the cache headers.
+    if let [item] = items.as_slice()
+        && item.success
+    {
+        let digest = image_digest(&std::fs::read(&form.files[0].file)?);
+        let if_none_match = req
+            .headers()
+            .get(header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok());
+        if etag_matches(if_none_match, &digest) {
+            return Ok(not_modified(&digest));
+        }
+        return Ok(ok_response(items, Some(&digest)));
+    }
+
+    Ok(ok_response(items, None))
+}
+
+/// Whether the caller opted into the structured (detailed) response via the
+/// explicit `?format=detailed` query parameter.
+///
+/// Gated on the query parameter alone: the default endpoint has always returned
+/// JSON, so negotiating on `Accept: application/json` would silently switch
+/// existing clients to the structured shape and break the flat-`Vec<String>`
+/// default.
+fn wants_detailed(query: &FormatQuery) -> bool {
+    query.format.as_deref() == Some("detailed")
+}
+
+/// Apply `recognize_fn` to each file in order, wrapping successes and per-item
+/// failures into [`BatchItem`]s without aborting the batch.
+fn batch_map<T, F>(files: &[TempFile], mut recognize_fn: F) -> Vec<BatchItem<T>>
+where
+    F: FnMut(&TempFile) -> Result<Vec<T>, AppError>,
+{
+    files
+        .iter()
+        .map(|file| {
+            let filename = file.file_name.clone().unwrap_or_default();
+            match recognize_fn(file) {
+                Ok(lines) => BatchItem {
+                    filename,
+                    success: true,
+                    lines: Some(lines),
+                    error: None,
+                },
+                Err(err) => {
+                    error!("Failed to recognize {}: {}", filename, err);
+                    BatchItem {
+                        filename,
+                        success: false,
+                        lines: None,
+                        error: Some(err.to_string()),
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
+/// Read a single uploaded file, enforce the per-file size cap, and resolve its
+/// recognized lines through the cache.
+fn recognize_file(state: &AppState, file: &TempFile) -> Result<Vec<String>, AppError> {
+    if file.size > MAX_IMAGE_BYTES {
+        return Err(AppError::PayloadTooLarge);
+    }
+
+    let image_bytes = std::fs::read(&file.file)?;
+    let declared = file.content_type.as_ref().map(|m| m.to_string());
+    let digest = image_digest(&image_bytes);
+
+    lookup_or_recognize(state, &digest, &image_bytes, declared.as_deref())
+}
+
+/// Like [`recognize_file`] but returns structured per-line geometry. The result
+/// cache only stores flat text, so detailed requests always run the pipeline.
+fn recognize_file_detailed(state: &AppState, file: &TempFile) -> Result<Vec<TextLine>, AppError> {
+    if file.size > MAX_IMAGE_BYTES {
+        return Err(AppError::PayloadTooLarge);
+    }
+
+    let image_bytes = std::fs::read(&file.file)?;
+    let declared = file.content_type.as_ref().map(|m| m.to_string());
+
+    recognize_bytes_detailed(&state.engine, &image_bytes, declared.as_deref())
+}
+
+/// Reject a URL before it is fetched server-side unless it is `http(s)`
+/// targeting a public address, to avoid an SSRF vector into
+/// internal/loopback services.
+///
+/// Only the scheme and literal-IP hosts are checked here. A literal IP is
+/// connected to directly — hyper's connector skips DNS resolution for it
+/// entirely — so it must be validated eagerly; there's no second resolution
+/// for a fixed address to race against. A hostname, by contrast, is resolved
+/// by [`PublicDnsResolver`] at connect time instead of here, because
+/// validating it here too would mean resolving it twice (once to validate,
+/// once to connect) and a DNS response that differs between the two lookups
+/// would bypass the guard entirely.
+fn validate_url(parsed: &reqwest::Url) -> Result<(), AppError> {
+    match parsed.scheme() {
+        "http" | "https" => {}
+        other => {
+            return Err(AppError::Download(
+                format!("unsupported URL scheme {}", other).into(),
+            ));
+        }
+    }
+
+    if let Some(ip) = parsed.host_str().and_then(|h| h.parse::<IpAddr>().ok())
+        && !is_global(ip)
+    {
+        return Err(AppError::Download(
+            format!("URL targets a non-public address {}", ip).into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// `reqwest::dns::Resolve` impl that refuses to hand back any non-public
+/// address, so the address validated is always the address connected to.
+///
+/// Resolving and validating in the same lookup closes the DNS-rebinding
+/// TOCTOU that a separate "resolve, validate, then let the HTTP client
+/// resolve again and connect" check is vulnerable to: a name with a short TTL
+/// can answer the first lookup with a public address and the second with
+/// `127.0.0.1` or the cloud metadata address, bypassing a check that ran
+/// against stale addresses.
+struct PublicDnsResolver;
+
+impl reqwest::dns::Resolve for PublicDnsResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(format!("{} did not resolve to any address", name.as_str()).into());
+            }
+
+            for addr in &addrs {
+                if !is_global(addr.ip()) {
+                    return Err(format!(
+                        "{} resolves to a non-public address {}",
+                        name.as_str(),
+                        addr.ip()
+                    )
+                    .into());
+                }
+            }
+
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// HTTP client for remote ingest that resolves every hostname — including
+/// every redirect hop — through [`PublicDnsResolver`], so a public URL cannot
+/// 3xx-redirect into a loopback/metadata target and bypass the guard.
+/// Redirect chains are also bounded to 10 hops.
+fn fetch_client() -> Result<reqwest::Client, AppError> {
+    let policy = reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() > 10 {
+            return attempt.error("too many redirects");
+        }
+        match validate_url(attempt.url()) {
+            Ok(()) => attempt.follow(),
+            Err(err) => attempt.error(err.to_string()),
+        }
+    });
+
+    reqwest::Client::builder()
+        .redirect(policy)
+        .dns_resolver(Arc::new(PublicDnsResolver))
+        .build()
+        .map_err(|e| AppError::Download(Box::new(e)))
+}
+
+/// Whether an IP address is a routable public unicast address, i.e. not
+/// loopback, unspecified, private, or link-local (nor, for IPv4, broadcast or
+/// documentation).
+fn is_global(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            !(v4.is_loopback()
+                || v4.is_unspecified()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_broadcast()
+                || v4.is_documentation())
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_global(IpAddr::V4(v4));
+            }
+            let segments = v6.segments();
+            let is_unique_local = (segments[0] & 0xfe00) == 0xfc00;
+            let is_link_local = (segments[0] & 0xffc0) == 0xfe80;
+            !(v6.is_loopback() || v6.is_unspecified() || is_unique_local || is_link_local)
+        }
+    }
+}
+
+/// Download an image from a remote URL and run the recognition pipeline over it.
+///
+/// Mirrors the async `fetch` path used by `download_model`, but enforces the
+/// same 15 MB cap as the upload handler by honouring `Content-Length` and
+/// aborting once the streamed body exceeds the limit.
+#[utoipa::path(
+    post,
+    path = "/v1/recognize/url",
+    tag = "recognition",
+    request_body = UrlRequest,
+    responses(
+        (status = 200, description = "Recognized text", body = ApiResponse<Vec<String>>),
+        (status = 304, description = "Not modified (matched If-None-Match)"),
+        (status = 400, description = "Download failed or remote is not an image"),
+    )
+)]
+#[post("/v1/recognize/url")]
+async fn recognize_url(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    body: web::Json<UrlRequest>,
+) -> Result<HttpResponse, AppError> {
+    let url = reqwest::Url::parse(&body.url).map_err(|e| AppError::Download(Box::new(e)))?;
+    validate_url(&url)?;
+
+    let mut response = fetch_client()?
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| AppError::Download(Box::new(e)))?;
+
+    if !response.status().is_success() {
+        return Err(AppError::Download(
+            format!("remote returned status {}", response.status()).into(),
+        ));
+    }
+
+    let declared = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+
+    if let Some(content_type) = declared.as_deref()
+        && !content_type.starts_with("image/")
+    {
+        return Err(AppError::Download(
+            format!("remote served non-image content type {}", content_type).into(),
+        ));
+    }
+
+    if let Some(len) = response.content_length()
+        && len as usize > MAX_IMAGE_BYTES
+    {
+        return Err(AppError::PayloadTooLarge);
+    }
+
+    let mut image_bytes: Vec<u8> = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Download(Box::new(e)))?
+    {
+        if image_bytes.len() + chunk.len() > MAX_IMAGE_BYTES {
+            return Err(AppError::PayloadTooLarge);
+        }
+        image_bytes.extend_from_slice(&chunk);
+    }
+
+    let if_none_match = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok());
+
+    Ok(process_image(
+        &state,
+        &image_bytes,
+        declared.as_deref(),
+        if_none_match,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "service",
+    responses((status = 200, description = "Service is healthy"))
+)]
 #[get("/health")]
 async fn health() -> impl Responder {
     HttpResponse::Ok().body("OK")
 }
 
+/// Machine-readable OpenAPI contract for the recognition API, consumable by
+/// OpenAPI-codegen toolchains to produce typed clients in any language.
+#[derive(OpenApi)]
+#[openapi(
+    paths(recognize, recognize_url, health),
+    components(schemas(
+        UrlRequest,
+        UploadForm,
+        WordBox,
+        TextLine,
+        BatchItem<String>,
+        BatchItem<TextLine>,
+        ApiResponse<Vec<String>>,
+        ApiResponse<Vec<BatchItem<String>>>,
+        ApiResponse<Vec<BatchItem<TextLine>>>,
+        RecognizeResponseBody,
+    )),
+    tags(
+        (name = "recognition", description = "OCR endpoints"),
+        (name = "service", description = "Service health")
+    )
+)]
+struct ApiDoc;
+
+#[get("/openapi.json")]
+async fn openapi_json() -> impl Responder {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
+
 #[actix_web::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     env_logger::init();
@@ -203,7 +953,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
         e
     })?;
 
-    let app_state = web::Data::new(AppState { engine });
+    let cache = Mutex::new(LruCache::new(
+        NonZeroUsize::new(CACHE_CAPACITY).expect("cache capacity must be non-zero"),
+    ));
+
+    let app_state = web::Data::new(AppState { engine, cache });
 
     HttpServer::new(move || {
         App::new()
@@ -211,13 +965,19 @@ async fn main() -> Result<(), Box<dyn Error>> {
             .wrap(ErrorHandlers::new().default_handler(global_error_handler))
             .app_data(
                 MultipartFormConfig::default()
-                    .total_limit(15 * 1024 * 1024) // 15 MB
-                    .memory_limit(15 * 1024 * 1024) // 15 MB
+                    .total_limit(MAX_REQUEST_BYTES)
+                    .memory_limit(MAX_REQUEST_BYTES)
                     .error_handler(handle_multipart_error),
             )
             .app_data(app_state.clone())
             .service(recognize)
+            .service(recognize_url)
             .service(health)
+            .service(openapi_json)
+            .service(
+                SwaggerUi::new("/docs/{_:.*}")
+                    .url("/openapi.json", ApiDoc::openapi()),
+            )
     })
     .bind("0.0.0.0:6622")?
     .run()
@@ -233,9 +993,44 @@ async fn download_model(url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
         let bytes = response.bytes().await?;
         Ok(bytes.to_vec())
     } else {
-        Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Failed to download model from {}", url),
-        )))
+        Err(Box::new(std::io::Error::other(format!(
+            "Failed to download model from {}",
+            url
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode an image to the given format and confirm `decode_image` reads it
+    /// back, proving the decoder feature is actually enabled rather than
+    /// degrading to a 415.
+    fn assert_roundtrips(format: ImageFormat, declared: &str) {
+        let original = image::RgbImage::from_pixel(8, 8, image::Rgb([12, 34, 56]));
+        let mut bytes = Vec::new();
+        image::DynamicImage::ImageRgb8(original)
+            .write_to(&mut Cursor::new(&mut bytes), format)
+            .expect("encode sample image");
+
+        let decoded = decode_image(&bytes, Some(declared)).expect("decode sample image");
+        assert_eq!(decoded.dimensions(), (8, 8));
+    }
+
+    #[test]
+    fn decodes_webp() {
+        assert_roundtrips(ImageFormat::WebP, "image/webp");
+    }
+
+    #[test]
+    fn decodes_avif() {
+        assert_roundtrips(ImageFormat::Avif, "image/avif");
+    }
+
+    #[test]
+    fn unsupported_bytes_are_415_not_400() {
+        let err = decode_image(b"not an image at all", Some("image/webp")).unwrap_err();
+        assert!(matches!(err, AppError::UnsupportedFormat { .. }));
     }
 }